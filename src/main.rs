@@ -1,24 +1,50 @@
-//! A shader that reads a mesh's custom vertex attribute.
+//! A small render graph with a compute prepass, an instanced mesh pass, and
+//! a post-process pass, wired together as render graph nodes: a plain node
+//! edge orders the compute prepass before the scene pass, and a named slot
+//! carries the scene's color output into the post-process pass.
 
 use bevy::{
+    ecs::{
+        query::ReadOnlyWorldQuery,
+        system::{lifetimeless::SRes, SystemParamItem},
+    },
     prelude::*,
     render::{
+        camera::ExtractedCamera,
         extract_resource::{ExtractResource, ExtractResourcePlugin},
         render_graph,
+        mesh::{GpuBufferInfo, Indices, MeshVertexAttribute, MeshVertexBufferLayout},
+        render_asset::RenderAssets,
+        render_phase::{
+            AddRenderCommand, CachedRenderPipelinePhaseItem, DrawFunctionId, DrawFunctions,
+            PhaseItem, RenderCommand, RenderCommandResult, RenderPhase, SetItemPipeline,
+            TrackedRenderPass,
+        },
         render_resource::{
-            BindGroup, BindGroupDescriptor, BindGroupLayout, BindGroupLayoutDescriptor, BlendState,
-            Buffer, BufferInitDescriptor, BufferUsages, CachedRenderPipelineId, ColorTargetState,
-            ColorWrites, FragmentState, LoadOp, MultisampleState, Operations, PipelineCache,
-            PrimitiveState, PrimitiveTopology, RenderPassDescriptor, RenderPipelineDescriptor,
-            TextureFormat, VertexAttribute, VertexBufferLayout, VertexFormat, VertexState,
+            BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+            BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType,
+            BlendState, Buffer, BufferBindingType, BufferDescriptor, BufferInitDescriptor,
+            BufferUsages, CachedComputePipelineId, CachedRenderPipelineId, ColorTargetState,
+            ColorWrites,
+            ComputePassDescriptor, ComputePipelineDescriptor, Extent3d, FragmentState, LoadOp,
+            MultisampleState, Operations, PipelineCache, PrimitiveState, PrimitiveTopology,
+            RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor, Sampler,
+            SamplerBindingType, SamplerDescriptor, Shader, ShaderDefVal, ShaderStages,
+            SpecializedMeshPipeline,
+            SpecializedMeshPipelineError, SpecializedMeshPipelines, TextureDescriptor,
+            TextureDimension, TextureFormat, TextureSampleType, TextureUsages, TextureView,
+            TextureViewDimension, VertexAttribute, VertexBufferLayout, VertexFormat, VertexState,
             VertexStepMode,
         },
-        renderer::{RenderContext, RenderDevice},
-        texture::BevyDefault,
+        renderer::{RenderContext, RenderDevice, RenderQueue},
+        texture::{BevyDefault, TextureCache},
         view::ViewTarget,
-        Render, RenderApp, RenderSet,
+        Extract, Render, RenderApp, RenderSet,
     },
+    utils::FloatOrd,
 };
+use bytemuck::{Pod, Zeroable};
+use std::ops::Range;
 
 fn main() {
     App::new()
@@ -27,12 +53,43 @@ fn main() {
         .run();
 }
 
-fn setup(mut commands: Commands, _meshes: ResMut<Assets<Mesh>>) {
+/// A custom per-vertex attribute alongside the builtin position attribute,
+/// read by `scene.wgsl` and tinting each corner of the triangle.
+const ATTRIBUTE_CUSTOM_COLOR: MeshVertexAttribute =
+    MeshVertexAttribute::new("CustomColor", 988540917, VertexFormat::Float32x4);
+
+/// Number of instanced copies of the mesh. Threaded into `compute.wgsl` as a
+/// shader def so the buffer size, the draw's instance count, and the
+/// compute dispatch all derive from this single constant.
+const INSTANCE_COUNT: usize = 10;
+
+fn setup(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
     // camera
     commands.spawn(Camera3dBundle {
         transform: Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
         ..default()
     });
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [1.0, 1.0, 0.0]],
+    );
+    mesh.insert_attribute(
+        ATTRIBUTE_CUSTOM_COLOR,
+        vec![
+            [1.0, 0.0, 0.0, 1.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0, 1.0],
+        ],
+    );
+    mesh.set_indices(Some(Indices::U32(vec![0, 1, 2])));
+
+    commands.spawn(meshes.add(mesh));
+}
+
+fn update_time(time: Res<Time>, mut my_render: ResMut<MyRender>) {
+    my_render.time = time.elapsed_seconds();
 }
 
 struct MyRenderPlugin;
@@ -40,67 +97,389 @@ struct MyRenderPlugin;
 impl Plugin for MyRenderPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<MyRender>()
-            .add_plugins(ExtractResourcePlugin::<MyRender>::default());
+            .add_plugins(ExtractResourcePlugin::<MyRender>::default())
+            .add_systems(Update, update_time);
 
         let render_app = app.sub_app_mut(RenderApp);
-        render_app.add_systems(Render, prepare_bind_group.in_set(RenderSet::Prepare));
+        render_app
+            .add_render_command::<MyPhaseItem, DrawMyPhaseItem>()
+            .init_resource::<DrawFunctions<MyPhaseItem>>()
+            .init_resource::<SpecializedMeshPipelines<MyScenePipeline>>()
+            .add_systems(ExtractSchedule, (extract_my_phase, extract_my_meshes))
+            .add_systems(
+                Render,
+                (
+                    prepare_scene_bindings.in_set(RenderSet::Prepare),
+                    queue_my_phase_item.in_set(RenderSet::Queue),
+                    sort_my_phase_item.in_set(RenderSet::PhaseSort),
+                ),
+            );
+
+        // Three passes, wired into a small graph: the compute prepass fills
+        // the instance buffer the scene pass reads, and the scene pass's
+        // offscreen color output feeds the post-process pass through a
+        // named slot rather than a shared resource, so the graph's
+        // topological sort - not system-ordering - decides execution order.
+        let compute_node = MyComputeNode;
+        let scene_node = MySceneNode::from_world(&mut render_app.world);
+        let post_process_node = MyPostProcessNode::from_world(&mut render_app.world);
 
-        let node =MyRenderNode::from_world(&mut render_app.world);
         let mut render_graph = render_app.world.resource_mut::<render_graph::RenderGraph>();
-        render_graph.add_node("my_render", node);
+        render_graph.add_node("my_compute", compute_node);
+        render_graph.add_node("my_scene", scene_node);
+        render_graph.add_node("my_post_process", post_process_node);
+        render_graph.add_node_edge("my_compute", "my_scene");
+        render_graph.add_slot_edge("my_scene", "color", "my_post_process", "color");
     }
 
     fn finish(&self, app: &mut App) {
         let render_app = app.sub_app_mut(RenderApp);
-        render_app.init_resource::<MyRenderPipeline>();
+        render_app
+            .init_resource::<GlobalsBuffer>()
+            .init_resource::<MyScenePipeline>()
+            .init_resource::<MyPostProcessPipeline>()
+            .init_resource::<MyComputePipeline>();
     }
 }
 
 #[derive(Resource, ExtractResource, Default, Clone)]
-struct MyRender {}
+struct MyRender {
+    time: f32,
+}
+
+/// Mirrors `scene.wgsl` and `post_process.wgsl`'s `Globals` uniform. Shared
+/// by both passes so they animate off the same clock.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct GlobalsUniform {
+    time: f32,
+}
+
+#[derive(Resource)]
+struct GlobalsBuffer(Buffer);
+
+impl FromWorld for GlobalsBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("globals_buffer"),
+            contents: bytemuck::bytes_of(&GlobalsUniform { time: 0.0 }),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        Self(buffer)
+    }
+}
+
+/// Per-instance placement data, written by the compute prepass and read by
+/// `scene.wgsl` to spread one mesh into many instanced copies.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct InstanceData {
+    position: Vec3,
+    scale: f32,
+    color: [f32; 4],
+}
+
+/// Drives the strength of the chromatic-aberration channel split applied by
+/// `post_process.wgsl`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct ChromaticAberration {
+    strength: f32,
+}
+
+/// A single instanced draw of the mesh, queued by [`queue_my_phase_item`]
+/// and executed by [`MySceneNode`] through its [`RenderPhase`].
+struct MyPhaseItem {
+    sort_key: f32,
+    entity: Entity,
+    draw_function: DrawFunctionId,
+    pipeline: CachedRenderPipelineId,
+    batch_range: Range<u32>,
+}
+
+impl PhaseItem for MyPhaseItem {
+    type SortKey = FloatOrd;
+
+    fn entity(&self) -> Entity {
+        self.entity
+    }
 
+    fn sort_key(&self) -> Self::SortKey {
+        FloatOrd(self.sort_key)
+    }
+
+    fn draw_function(&self) -> DrawFunctionId {
+        self.draw_function
+    }
+
+    fn batch_range(&self) -> &Range<u32> {
+        &self.batch_range
+    }
+
+    fn batch_range_mut(&mut self) -> &mut Range<u32> {
+        &mut self.batch_range
+    }
+}
+
+impl CachedRenderPipelinePhaseItem for MyPhaseItem {
+    fn cached_pipeline(&self) -> CachedRenderPipelineId {
+        self.pipeline
+    }
+}
+
+/// Binds the scene pass's bind group at slot `I`.
+struct SetMyBindGroup<const I: usize>;
+
+impl<const I: usize> RenderCommand<MyPhaseItem> for SetMyBindGroup<I> {
+    type Param = SRes<MySceneBindings>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = ();
+
+    fn render<'w>(
+        _item: &MyPhaseItem,
+        _view: (),
+        _entity: (),
+        bindings: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        pass.set_bind_group(I, &bindings.into_inner().bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+/// Binds the mesh's own vertex/index buffers plus the instance buffer, then
+/// issues the instanced (indexed, if available) draw call.
+struct DrawMesh;
+
+impl RenderCommand<MyPhaseItem> for DrawMesh {
+    type Param = (SRes<RenderAssets<Mesh>>, SRes<MySceneBindings>);
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = &'static Handle<Mesh>;
+
+    fn render<'w>(
+        _item: &MyPhaseItem,
+        _view: (),
+        mesh_handle: &'w Handle<Mesh>,
+        (meshes, bindings): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(gpu_mesh) = meshes.into_inner().get(mesh_handle) else {
+            return RenderCommandResult::Failure;
+        };
+        let bindings = bindings.into_inner();
+
+        pass.set_vertex_buffer(0, gpu_mesh.vertex_buffer.slice(..));
+        pass.set_vertex_buffer(1, bindings.instance_buffer.slice(..));
+
+        match &gpu_mesh.buffer_info {
+            GpuBufferInfo::Indexed {
+                buffer,
+                count,
+                index_format,
+            } => {
+                pass.set_index_buffer(buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(0..*count, 0, 0..bindings.instance_count);
+            }
+            GpuBufferInfo::NonIndexed => {
+                pass.draw(0..gpu_mesh.vertex_count, 0..bindings.instance_count);
+            }
+        }
+
+        RenderCommandResult::Success
+    }
+}
+
+type DrawMyPhaseItem = (SetItemPipeline, SetMyBindGroup<0>, DrawMesh);
+
+/// Pipeline for the scene pass: draws the instanced mesh into an offscreen
+/// color target handed off to [`MyPostProcessPipeline`] through a render
+/// graph slot.
 #[derive(Resource)]
-struct MyRenderPipeline {
+struct MyScenePipeline {
     bind_group_layout: BindGroupLayout,
-    render_pipeline: CachedRenderPipelineId,
+    shader: Handle<Shader>,
 }
 
-impl FromWorld for MyRenderPipeline {
+impl FromWorld for MyScenePipeline {
     fn from_world(world: &mut World) -> Self {
         let render_device = world.resource::<RenderDevice>();
 
         let bind_group_layout =
             render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-                label: None,
-                entries: &[],
+                label: Some("my_scene_bind_group_layout"),
+                entries: &[BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX_FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
             });
 
-        let shader = world.resource::<AssetServer>().load("shader.wgsl");
-        let pipeline_cache = world.resource::<PipelineCache>();
-        let render_pipeline = pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
-            label: Some("my_render_pipeline".into()),
-            layout: vec![],
+        let shader = world.resource::<AssetServer>().load("scene.wgsl");
+
+        Self {
+            bind_group_layout,
+            shader,
+        }
+    }
+}
+
+/// Per-instance vertex buffer layout, bound alongside the mesh's own layout
+/// at slot 1.
+fn instance_buffer_layout() -> VertexBufferLayout {
+    VertexBufferLayout {
+        array_stride: std::mem::size_of::<InstanceData>() as u64,
+        step_mode: VertexStepMode::Instance,
+        attributes: vec![
+            VertexAttribute {
+                format: VertexFormat::Float32x3,
+                offset: 0,
+                shader_location: 2,
+            },
+            VertexAttribute {
+                format: VertexFormat::Float32,
+                offset: 4 * 3,
+                shader_location: 3,
+            },
+            VertexAttribute {
+                format: VertexFormat::Float32x4,
+                offset: 4 * 4,
+                shader_location: 4,
+            },
+        ],
+    }
+}
+
+impl SpecializedMeshPipeline for MyScenePipeline {
+    type Key = ();
+
+    fn specialize(
+        &self,
+        _key: Self::Key,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mesh_vertex_layout = layout.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            ATTRIBUTE_CUSTOM_COLOR.at_shader_location(1),
+        ])?;
+
+        Ok(RenderPipelineDescriptor {
+            label: Some("my_scene_pipeline".into()),
+            layout: vec![self.bind_group_layout.clone()],
             push_constant_ranges: Vec::new(),
-            primitive: PrimitiveState {
-                topology: PrimitiveTopology::TriangleStrip,
-                ..default()
+            primitive: PrimitiveState::default(),
+            vertex: VertexState {
+                shader: self.shader.clone(),
+                entry_point: "vertex".into(),
+                shader_defs: vec![],
+                buffers: vec![mesh_vertex_layout, instance_buffer_layout()],
             },
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                entry_point: "fragment".into(),
+                shader_defs: vec![],
+                targets: vec![Some(ColorTargetState {
+                    format: TextureFormat::bevy_default(),
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+        })
+    }
+}
+
+/// Pipeline for the post-process pass: a fullscreen triangle that samples
+/// the scene pass's color output (via the `color` slot) and writes the
+/// view's final image.
+#[derive(Resource)]
+struct MyPostProcessPipeline {
+    bind_group_layout: BindGroupLayout,
+    sampler: Sampler,
+    aberration_buffer: Buffer,
+    pipeline: CachedRenderPipelineId,
+}
+
+impl FromWorld for MyPostProcessPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let bind_group_layout =
+            render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("my_post_process_bind_group_layout"),
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Float { filterable: true },
+                            view_dimension: TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+
+        let aberration_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("chromatic_aberration_buffer"),
+            contents: bytemuck::bytes_of(&ChromaticAberration { strength: 0.01 }),
+            usage: BufferUsages::UNIFORM,
+        });
+
+        let shader = world.resource::<AssetServer>().load("post_process.wgsl");
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let pipeline = pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+            label: Some("my_post_process_pipeline".into()),
+            layout: vec![bind_group_layout.clone()],
+            push_constant_ranges: Vec::new(),
+            primitive: PrimitiveState::default(),
             vertex: VertexState {
                 shader: shader.clone(),
                 entry_point: "vertex".into(),
                 shader_defs: vec![],
-                buffers: vec![VertexBufferLayout {
-                    array_stride: 4 * 2,
-                    step_mode: VertexStepMode::Vertex,
-                    attributes: vec![VertexAttribute {
-                        format: VertexFormat::Float32x2,
-                        offset: 0,
-                        shader_location: 0,
-                    }],
-                }],
+                buffers: vec![],
             },
             fragment: Some(FragmentState {
-                shader: shader.clone(),
+                shader,
                 entry_point: "fragment".into(),
                 shader_defs: vec![],
                 targets: vec![Some(ColorTargetState {
@@ -119,101 +498,410 @@ impl FromWorld for MyRenderPipeline {
 
         Self {
             bind_group_layout,
-            render_pipeline,
+            sampler,
+            aberration_buffer,
+            pipeline,
         }
     }
 }
 
 #[derive(Resource)]
-struct MyRenderBindings {
-    vertex_buffer: Buffer,
+struct MyComputePipeline {
+    bind_group_layout: BindGroupLayout,
+    pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for MyComputePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let bind_group_layout =
+            render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("my_compute_bind_group_layout"),
+                entries: &[BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let shader = world.resource::<AssetServer>().load("compute.wgsl");
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("my_compute_pipeline".into()),
+            layout: vec![bind_group_layout.clone()],
+            push_constant_ranges: Vec::new(),
+            shader,
+            shader_defs: vec![ShaderDefVal::UInt(
+                "INSTANCE_COUNT".into(),
+                INSTANCE_COUNT as u32,
+            )],
+            entry_point: "compute".into(),
+        });
+
+        Self {
+            bind_group_layout,
+            pipeline,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct MyComputeBindings {
+    bind_group: BindGroup,
+}
+
+/// Bindings for the scene pass: the globals bind group, the instance buffer
+/// the compute prepass fills, and the offscreen color target the scene pass
+/// renders into and hands to the post-process pass.
+#[derive(Resource)]
+struct MySceneBindings {
     bind_group: BindGroup,
+    instance_buffer: Buffer,
+    instance_count: u32,
+    color_view: TextureView,
 }
 
-fn prepare_bind_group(
-    _splat: Res<MyRender>,
-    pipeline: Res<MyRenderPipeline>,
+/// Ensures every 3D camera in the render world carries a [`RenderPhase`] for
+/// [`MyPhaseItem`] so systems in later render sets have somewhere to queue
+/// draws into.
+fn extract_my_phase(mut commands: Commands, cameras: Extract<Query<Entity, With<Camera3d>>>) {
+    for entity in &cameras {
+        commands
+            .get_or_spawn(entity)
+            .insert(RenderPhase::<MyPhaseItem>::default());
+    }
+}
+
+/// Mirrors entities carrying a mesh into the render world so
+/// [`queue_my_phase_item`] and [`DrawMesh`] can read their GPU data.
+fn extract_my_meshes(mut commands: Commands, meshes: Extract<Query<(Entity, &Handle<Mesh>)>>) {
+    for (entity, mesh) in &meshes {
+        commands.get_or_spawn(entity).insert(mesh.clone());
+    }
+}
+
+fn queue_my_phase_item(
+    draw_functions: Res<DrawFunctions<MyPhaseItem>>,
+    my_pipeline: Res<MyScenePipeline>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<MyScenePipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    render_meshes: Res<RenderAssets<Mesh>>,
+    mesh_entities: Query<(Entity, &Handle<Mesh>)>,
+    mut views: Query<&mut RenderPhase<MyPhaseItem>>,
+) {
+    let draw_function = draw_functions.read().id::<DrawMyPhaseItem>();
+
+    for mut phase in &mut views {
+        for (entity, mesh_handle) in &mesh_entities {
+            let Some(gpu_mesh) = render_meshes.get(mesh_handle) else {
+                continue;
+            };
+            let Ok(pipeline) =
+                pipelines.specialize(&pipeline_cache, &my_pipeline, (), &gpu_mesh.layout)
+            else {
+                continue;
+            };
+
+            phase.add(MyPhaseItem {
+                sort_key: 0.0,
+                entity,
+                draw_function,
+                pipeline,
+                batch_range: 0..1,
+            });
+        }
+    }
+}
+
+fn sort_my_phase_item(mut phases: Query<&mut RenderPhase<MyPhaseItem>>) {
+    for mut phase in &mut phases {
+        MyPhaseItem::sort(&mut phase.items);
+    }
+}
+
+fn prepare_scene_bindings(
+    my_render: Res<MyRender>,
+    globals_buffer: Res<GlobalsBuffer>,
+    scene_pipeline: Res<MyScenePipeline>,
+    compute_pipeline: Res<MyComputePipeline>,
     render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut texture_cache: ResMut<TextureCache>,
+    cameras: Query<&ExtractedCamera>,
     mut commands: Commands,
 ) {
-    let verts = [
-        Vec2::new(-1.0, -1.0),
-        Vec2::new(1.0, -1.0),
-        Vec2::new(1.0, 1.0),
-    ];
-    let vertex_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
-        label: None,
-        contents: bytemuck::cast_slice(&verts),
-        usage: BufferUsages::VERTEX,
+    render_queue.write_buffer(
+        &globals_buffer.0,
+        0,
+        bytemuck::bytes_of(&GlobalsUniform {
+            time: my_render.time,
+        }),
+    );
+
+    // Left uninitialized: MyComputeNode is the sole writer of instance data,
+    // filling this buffer from scratch every frame before MySceneNode reads it.
+    let instance_buffer = render_device.create_buffer(&BufferDescriptor {
+        label: Some("instance_buffer"),
+        size: (INSTANCE_COUNT * std::mem::size_of::<InstanceData>()) as u64,
+        usage: BufferUsages::STORAGE | BufferUsages::VERTEX,
+        mapped_at_creation: false,
+    });
+
+    let compute_bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+        label: Some("my_compute_bind_group"),
+        layout: &compute_pipeline.bind_group_layout,
+        entries: &[BindGroupEntry {
+            binding: 0,
+            resource: instance_buffer.as_entire_binding(),
+        }],
     });
+
     let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
-        label: None,
-        layout: &pipeline.bind_group_layout,
-        entries: &[],
+        label: Some("my_scene_bind_group"),
+        layout: &scene_pipeline.bind_group_layout,
+        entries: &[BindGroupEntry {
+            binding: 0,
+            resource: globals_buffer.0.as_entire_binding(),
+        }],
     });
-    commands.insert_resource(MyRenderBindings {
-        vertex_buffer,
+
+    let size = cameras
+        .iter()
+        .find_map(|camera| camera.physical_target_size)
+        .unwrap_or(UVec2::new(1, 1));
+    // `TextureCache` reuses the existing texture whenever the descriptor
+    // (including size) is unchanged, instead of allocating a fresh one
+    // every frame.
+    let color_texture = texture_cache.get(
+        &render_device,
+        TextureDescriptor {
+            label: Some("my_scene_color_texture"),
+            size: Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::bevy_default(),
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+    );
+    let color_view = color_texture.default_view.clone();
+
+    commands.insert_resource(MySceneBindings {
         bind_group,
+        instance_buffer,
+        instance_count: INSTANCE_COUNT as u32,
+        color_view,
+    });
+    commands.insert_resource(MyComputeBindings {
+        bind_group: compute_bind_group,
     });
 }
 
-struct MyRenderNode {
-    view_target_query: QueryState<&'static ViewTarget>,
+struct MyComputeNode;
+
+impl render_graph::Node for MyComputeNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let bindings = world.resource::<MyComputeBindings>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let pipeline = world.resource::<MyComputePipeline>();
+
+        let Some(compute_pipeline) = pipeline_cache.get_compute_pipeline(pipeline.pipeline) else {
+            return Ok(());
+        };
+
+        let mut pass = render_context
+            .command_encoder()
+            .begin_compute_pass(&ComputePassDescriptor {
+                label: Some("my_compute_pass"),
+            });
+
+        pass.set_bind_group(0, &bindings.bind_group, &[]);
+        pass.set_pipeline(compute_pipeline);
+        pass.dispatch_workgroups(1, 1, 1);
+
+        Ok(())
+    }
+}
+
+/// Returns the single view entity matched by `view_query`, panicking if
+/// there isn't exactly one. This example only ever renders one camera.
+fn single_view_entity<F: ReadOnlyWorldQuery>(
+    view_query: &QueryState<Entity, F>,
+    world: &World,
+) -> Entity {
+    let mut views = view_query.iter_manual(world);
+    let view_entity = views.next().unwrap();
+    assert!(views.next().is_none());
+    view_entity
+}
+
+/// Renders the instanced mesh into an offscreen color target and publishes
+/// it on its `color` output slot for [`MyPostProcessNode`] to consume.
+struct MySceneNode {
+    view_query: QueryState<Entity, With<RenderPhase<MyPhaseItem>>>,
 }
 
-impl FromWorld for MyRenderNode {
+impl FromWorld for MySceneNode {
     fn from_world(world: &mut World) -> Self {
         Self {
-            view_target_query: QueryState::new(world),
+            view_query: QueryState::new(world),
         }
     }
 }
 
-impl render_graph::Node for MyRenderNode {
+impl render_graph::Node for MySceneNode {
+    fn output(&self) -> Vec<render_graph::SlotInfo> {
+        vec![render_graph::SlotInfo::new(
+            "color",
+            render_graph::SlotType::TextureView,
+        )]
+    }
+
     fn update(&mut self, world: &mut World) {
-        self.view_target_query.update_archetypes(world);
+        self.view_query.update_archetypes(world);
     }
 
     fn run(
         &self,
-        _graph: &mut render_graph::RenderGraphContext,
+        graph: &mut render_graph::RenderGraphContext,
         render_context: &mut RenderContext,
         world: &World,
     ) -> Result<(), render_graph::NodeRunError> {
-        let MyRenderBindings {
-            vertex_buffer,
-            bind_group,
-        } = world.resource();
-        let pipeline_cache = world.resource::<PipelineCache>();
-        let pipeline = world.resource::<MyRenderPipeline>();
+        let scene_bindings = world.resource::<MySceneBindings>();
 
-        let view = {
-            let mut views = self.view_target_query.iter_manual(world);
-            let v = views.next().unwrap();
-            assert!(views.next().is_none());
-            v
-        };
+        let view_entity = single_view_entity(&self.view_query, world);
+        let phase = world
+            .get::<RenderPhase<MyPhaseItem>>(view_entity)
+            .unwrap();
 
-        let mut pass = render_context
+        let render_pass = render_context
             .command_encoder()
             .begin_render_pass(&RenderPassDescriptor {
-                label: Some("my_render_pass"),
-                color_attachments: &[Some(view.get_unsampled_color_attachment(Operations {
-                    load: LoadOp::Clear(Color::BLACK.into()),
-                    store: true,
-                }))],
+                label: Some("my_scene_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &scene_bindings.color_view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(Color::BLACK.into()),
+                        store: true,
+                    },
+                })],
                 depth_stencil_attachment: None,
             });
+        let mut tracked_pass = TrackedRenderPass::new(render_context.render_device(), render_pass);
 
-        pass.set_vertex_buffer(0, (*vertex_buffer.slice(..)).clone());
-        pass.set_bind_group(0, bind_group, &[]);
+        phase.render(&mut tracked_pass, world, view_entity);
 
-        let render_pipeline = pipeline_cache
-            .get_render_pipeline(pipeline.render_pipeline)
-            .unwrap();
-        pass.set_pipeline(render_pipeline);
+        graph.set_output("color", scene_bindings.color_view.clone())?;
+        Ok(())
+    }
+}
+
+/// Consumes the scene pass's `color` input slot and writes it, with a
+/// chromatic aberration effect applied, over the view's final image.
+struct MyPostProcessNode {
+    view_query: QueryState<Entity, With<ViewTarget>>,
+}
 
-        pass.draw(0..3, 0..1);
+impl FromWorld for MyPostProcessNode {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            view_query: QueryState::new(world),
+        }
+    }
+}
+
+impl render_graph::Node for MyPostProcessNode {
+    fn input(&self) -> Vec<render_graph::SlotInfo> {
+        vec![render_graph::SlotInfo::new(
+            "color",
+            render_graph::SlotType::TextureView,
+        )]
+    }
+
+    fn update(&mut self, world: &mut World) {
+        self.view_query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let scene_color = graph.get_input_texture("color")?;
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let post_process_pipeline = world.resource::<MyPostProcessPipeline>();
+        let globals_buffer = world.resource::<GlobalsBuffer>();
+
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(post_process_pipeline.pipeline)
+        else {
+            return Ok(());
+        };
+
+        let view_entity = single_view_entity(&self.view_query, world);
+        let view_target = world.get::<ViewTarget>(view_entity).unwrap();
+        let post_process = view_target.post_process_write();
+
+        let bind_group = render_context
+            .render_device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("my_post_process_bind_group"),
+                layout: &post_process_pipeline.bind_group_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(scene_color),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&post_process_pipeline.sampler),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: post_process_pipeline.aberration_buffer.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: globals_buffer.0.as_entire_binding(),
+                    },
+                ],
+            });
+
+        let render_pass = render_context
+            .command_encoder()
+            .begin_render_pass(&RenderPassDescriptor {
+                label: Some("my_post_process_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: post_process.destination,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(Color::BLACK.into()),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+            });
+        let mut tracked_pass = TrackedRenderPass::new(render_context.render_device(), render_pass);
+        tracked_pass.set_render_pipeline(pipeline);
+        tracked_pass.set_bind_group(0, &bind_group, &[]);
+        tracked_pass.draw(0..3, 0..1);
 
         Ok(())
     }